@@ -1,10 +1,9 @@
 use crate::cli::TrinConfig;
+use crate::ipc::{cleanup_ipc_path, IpcListener};
 use reqwest::blocking as reqwest;
 use serde::{Deserialize, Serialize};
-use std::fs;
 use std::io::{self, Read, Write};
 use std::net::{TcpListener, TcpStream};
-use std::os::unix;
 use std::sync::Mutex;
 use std::{panic, process};
 use threadpool::ThreadPool;
@@ -45,7 +44,7 @@ fn set_ipc_cleanup_handlers(ipc_path: &str) {
 
     ctrlc::set_handler(move || {
         let ipc_path: &str = &*IPC_PATH.lock().unwrap().clone();
-        fs::remove_file(&ipc_path).unwrap();
+        cleanup_ipc_path(ipc_path);
         std::process::exit(1);
     })
     .expect("Error setting Ctrl-C handler.");
@@ -53,14 +52,14 @@ fn set_ipc_cleanup_handlers(ipc_path: &str) {
     let original_panic = panic::take_hook();
     panic::set_hook(Box::new(move |panic_info| {
         let ipc_path: &str = &*IPC_PATH.lock().unwrap().clone();
-        fs::remove_file(&ipc_path).unwrap();
+        cleanup_ipc_path(ipc_path);
         original_panic(panic_info);
         process::exit(1);
     }));
 }
 
 fn launch_ipc_client(pool: ThreadPool, infura_project_id: String, ipc_path: &str) {
-    let listener_result = unix::net::UnixListener::bind(ipc_path);
+    let listener_result = IpcListener::bind(ipc_path);
     let listener = match listener_result {
         Ok(listener) => {
             set_ipc_cleanup_handlers(ipc_path);